@@ -0,0 +1,147 @@
+//! Tracking of the protocol stacks we are currently listening on.
+//!
+//! AutoNAT asks a server to dial us back on a candidate address. There is no point requesting a
+//! dial-back over a protocol stack we do not listen on (e.g. asking for a QUIC dial when we only
+//! listen on TCP): such a test is guaranteed to fail and wastes the server's dial budget. This
+//! module keeps track of the listen addresses reported by the swarm so candidate addresses can be
+//! matched against an actual listener before they are probed.
+//!
+//! Matching reuses the same `clean_multiaddr` semantics as `libp2p_swarm::listener_presence`: the
+//! host/addressing tags (`dns*`/`ip4`/`ip6`/`p2p`) are stripped before comparison, so a
+//! `/dns4/example/tcp/443` listener still matches an `/ip4/1.2.3.4/tcp/443` candidate.
+
+use std::collections::HashMap;
+
+use libp2p_core::{transport::ListenerId, Multiaddr};
+
+fn is_not_protocol(tag: &str) -> bool {
+    // Keep this list in sync with `libp2p_swarm::listener_presence`: strip the host/addressing
+    // tags so a candidate matches a listener across dns-vs-ip and regardless of the trailing peer
+    // id.
+    !matches!(tag, "dns" | "dns4" | "dns6" | "dnsaddr" | "ip4" | "ip6" | "p2p")
+}
+
+/// Turn a multiaddress into the sequence of its transport protocols, dropping host/addressing tags.
+fn clean_multiaddr(address: &Multiaddr) -> Vec<&'static str> {
+    address
+        .protocol_stack()
+        .filter(|tag| is_not_protocol(tag))
+        .collect()
+}
+
+/// Keeps track of the addresses we are listening on, keyed by their [`ListenerId`], and answers
+/// whether a given address has a listener with a matching protocol stack.
+#[derive(Debug, Default)]
+pub(crate) struct ListenerPresence {
+    listeners: HashMap<ListenerId, Multiaddr>,
+}
+
+impl ListenerPresence {
+    /// Record a newly reported listen address.
+    pub(crate) fn on_new_listen_addr(&mut self, id: ListenerId, addr: Multiaddr) {
+        self.listeners.insert(id, addr);
+    }
+
+    /// Forget a listen address that has expired.
+    pub(crate) fn on_expired_listen_addr(&mut self, id: &ListenerId) {
+        self.listeners.remove(id);
+    }
+
+    /// Whether no listen address is currently known.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// Whether we have a listener whose protocol stack matches `addr`.
+    pub(crate) fn contains(&self, addr: &Multiaddr) -> bool {
+        let stack = clean_multiaddr(addr);
+        self.listeners
+            .values()
+            .any(|listen_addr| clean_multiaddr(listen_addr) == stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use libp2p_core::multiaddr::multiaddr;
+    use libp2p_core::transport::ListenerId;
+    use libp2p_core::Multiaddr;
+    use libp2p_identity::PeerId;
+
+    use super::ListenerPresence;
+
+    fn presence(addrs: impl IntoIterator<Item = Multiaddr>) -> ListenerPresence {
+        let mut presence = ListenerPresence::default();
+        for addr in addrs {
+            presence.on_new_listen_addr(ListenerId::next(), addr);
+        }
+        presence
+    }
+
+    #[test]
+    fn basic_ops() {
+        let bootstrap_libp2p_node_peer_id =
+            PeerId::from_str("QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN").unwrap();
+        let test_addrs = [
+            multiaddr!(Ip4([127, 0, 0, 1]), Tcp(1234u16)),
+            multiaddr!(Ip6([11, 22, 33, 44, 55, 66, 77, 88]), Udp(199u16), Tls, Quic),
+            multiaddr!(Dns4("heise.de"), Tcp(443u16), Tls, Https),
+            multiaddr!(Dnsaddr("bootstrap.libp2p.io"), P2p(bootstrap_libp2p_node_peer_id)),
+            multiaddr!(
+                Ip4([104, 131, 131, 82]),
+                Udp(4001u16),
+                Quic,
+                P2p(bootstrap_libp2p_node_peer_id)
+            ),
+        ];
+        let presence = presence(test_addrs.iter().cloned());
+        assert!(
+            test_addrs.iter().all(|addr| presence.contains(addr)),
+            "Basic input operations are not working. Likely cleaning function is not pure."
+        );
+    }
+
+    #[test]
+    fn reducing_functionality() {
+        let build_up_address = [
+            multiaddr!(Dnsaddr("libp2p.io"), Tls, Tcp(10u16)),
+            multiaddr!(Dnsaddr("libp2p.io"), Tls, Tcp(12u16), Udp(13u16), Quic),
+            multiaddr!(Ip4([1, 1, 1, 1]), Udp(100u16)),
+        ];
+        let presence = presence(build_up_address.iter().cloned());
+        assert!(build_up_address.iter().all(|addr| presence.contains(addr)));
+        assert!(presence.contains(&multiaddr!(Dns4("libp2p.io"), Tls, Tcp(10u16))));
+        assert!(presence.contains(&multiaddr!(
+            Dns4("libp2p.io"),
+            Tls,
+            Tcp(10u16),
+            Dnsaddr("bootstrap.libp2p.io")
+        )));
+        assert!(presence.contains(&multiaddr!(Dns("one.one.one.one"), Tls, Tcp(100u16))));
+        assert!(!presence.contains(&multiaddr!(Dns("one.one.one.one"), Tcp(100u16))));
+        assert!(!presence.contains(&multiaddr!(Dnsaddr("libp2p.io"), Tcp(10u16), Tls)));
+        assert!(!presence.contains(&multiaddr!(
+            Dnsaddr("libp2p.io"),
+            Quic,
+            Udp(13u16),
+            Tcp(12u16),
+            Tls
+        )));
+        assert!(!presence.contains(&multiaddr!(Dnsaddr("one.one.one.one"), Udp(100u16), Tls)));
+    }
+
+    #[test]
+    fn ignores_host_tags_across_dns_and_ip() {
+        let mut presence = ListenerPresence::default();
+        let id = ListenerId::next();
+        presence.on_new_listen_addr(id, multiaddr!(Dns4("example.com"), Tcp(443u16)));
+
+        assert!(presence.contains(&multiaddr!(Ip4([1, 2, 3, 4]), Tcp(443u16))));
+
+        presence.on_expired_listen_addr(&id);
+        assert!(presence.is_empty());
+        assert!(!presence.contains(&multiaddr!(Ip4([1, 2, 3, 4]), Tcp(443u16))));
+    }
+}
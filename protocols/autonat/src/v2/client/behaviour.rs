@@ -1,7 +1,7 @@
 use std::{
     collections::{HashMap, HashSet, VecDeque},
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use either::Either;
@@ -9,15 +9,22 @@ use futures::FutureExt;
 use futures_timer::Delay;
 use libp2p_core::{multiaddr::Protocol, transport::PortUse, Endpoint, Multiaddr};
 use libp2p_identity::PeerId;
+use prometheus_client::encoding::{EncodeLabelSet, EncodeLabelValue};
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::registry::Registry;
 use libp2p_swarm::{
     behaviour::{ConnectionEstablished, ExternalAddrConfirmed},
-    ConnectionClosed, ConnectionDenied, ConnectionHandler, ConnectionId, DialFailure, FromSwarm,
-    NetworkBehaviour, NewExternalAddrCandidate, NotifyHandler, ToSwarm,
+    ConnectionClosed, ConnectionDenied, ConnectionHandler, ConnectionId, DialFailure,
+    ExpiredListenAddr, FromSwarm, NetworkBehaviour, NewExternalAddrCandidate, NewListenAddr,
+    NotifyHandler, ToSwarm,
 };
 use rand::prelude::*;
 use rand_core::OsRng;
 use std::fmt::{Debug, Display, Formatter};
 
+use crate::listener_presence::ListenerPresence;
 use crate::v2::client::handler::dial_request::InternalError;
 use crate::v2::{global_only::IpExt, protocol::DialRequest};
 
@@ -34,6 +41,32 @@ pub struct Config {
 
     /// The interval at which we will attempt to confirm candidates as external addresses.
     pub(crate) probe_interval: Duration,
+
+    /// The maximum confidence we accumulate for a reachability status before capping it.
+    pub(crate) confidence_max: usize,
+
+    /// The confidence that has to be reached before a [`NatStatus`] change is reported via
+    /// [`Event::StatusChanged`]. Guards against flapping on a single stray test result.
+    pub(crate) confidence_threshold: usize,
+
+    /// The probe interval used while the reachability status is still `Unknown` or below the
+    /// confidence threshold.
+    pub(crate) retry_interval: Duration,
+
+    /// The probe interval used once the reachability status is confirmed with enough confidence.
+    pub(crate) refresh_interval: Duration,
+
+    /// How long a confirmed external address is trusted before it is re-validated. A confirmed
+    /// address older than this is scheduled for another dial-back probe.
+    pub(crate) address_ttl: Duration,
+
+    /// Minimum time that has to elapse before the same server is probed again. Prevents hammering
+    /// a single server while others sit idle.
+    pub(crate) per_server_cooldown: Duration,
+
+    /// Number of server-side failures (a misbehaving server, not an unreachable address) after
+    /// which a server is dropped from the rotation. A successful test resets the count.
+    pub(crate) max_server_failures: usize,
 }
 
 impl Config {
@@ -50,6 +83,55 @@ impl Config {
             ..self
         }
     }
+
+    pub fn with_confidence_max(self, confidence_max: usize) -> Self {
+        Self {
+            confidence_max,
+            ..self
+        }
+    }
+
+    pub fn with_confidence_threshold(self, confidence_threshold: usize) -> Self {
+        Self {
+            confidence_threshold,
+            ..self
+        }
+    }
+
+    pub fn with_retry_interval(self, retry_interval: Duration) -> Self {
+        Self {
+            retry_interval,
+            ..self
+        }
+    }
+
+    pub fn with_refresh_interval(self, refresh_interval: Duration) -> Self {
+        Self {
+            refresh_interval,
+            ..self
+        }
+    }
+
+    pub fn with_address_ttl(self, address_ttl: Duration) -> Self {
+        Self {
+            address_ttl,
+            ..self
+        }
+    }
+
+    pub fn with_per_server_cooldown(self, per_server_cooldown: Duration) -> Self {
+        Self {
+            per_server_cooldown,
+            ..self
+        }
+    }
+
+    pub fn with_max_server_failures(self, max_server_failures: usize) -> Self {
+        Self {
+            max_server_failures,
+            ..self
+        }
+    }
 }
 
 impl Default for Config {
@@ -57,6 +139,13 @@ impl Default for Config {
         Self {
             max_candidates: 10,
             probe_interval: Duration::from_secs(5),
+            confidence_max: 3,
+            confidence_threshold: 2,
+            retry_interval: Duration::from_secs(5),
+            refresh_interval: Duration::from_secs(15 * 60),
+            address_ttl: Duration::from_secs(30 * 60),
+            per_server_cooldown: Duration::from_secs(90),
+            max_server_failures: 3,
         }
     }
 }
@@ -78,6 +167,18 @@ where
     already_tested: HashSet<Multiaddr>,
     next_tick: Delay,
     peer_info: HashMap<ConnectionId, ConnectionInfo>,
+    /// The aggregate reachability status derived from the individual test results.
+    nat_status: NatStatus,
+    /// How confident we currently are in [`Behaviour::nat_status`], capped at
+    /// [`Config::confidence_max`].
+    confidence: usize,
+    /// The last [`NatStatus`] reported to the application via [`Event::StatusChanged`].
+    reported_status: NatStatus,
+    /// Optional Prometheus instrumentation, enabled via [`Behaviour::with_metrics`].
+    metrics: Option<Metrics>,
+    /// The protocol stacks we are currently listening on, used to skip candidates we could never
+    /// be dialed back on.
+    listener_presence: ListenerPresence,
 }
 
 impl<R> NetworkBehaviour for Behaviour<R>
@@ -120,6 +221,9 @@ where
             FromSwarm::ExternalAddrConfirmed(ExternalAddrConfirmed { addr }) => {
                 if let Some(info) = self.address_candidates.get_mut(addr) {
                     info.is_tested = true;
+                    info.was_confirmed = true;
+                    info.last_tested = Some(Instant::now());
+                    info.source = AddressSource::ConfirmedBySwarm;
                 }
             }
             FromSwarm::ConnectionEstablished(ConnectionEstablished {
@@ -134,6 +238,8 @@ where
                         peer_id,
                         supports_autonat: false,
                         is_local: addr_is_local(endpoint.get_remote_address()),
+                        last_probed: None,
+                        failures: 0,
                     });
             }
             FromSwarm::ConnectionClosed(ConnectionClosed {
@@ -150,6 +256,13 @@ where
             }) => {
                 self.handle_no_connection(peer_id, connection_id);
             }
+            FromSwarm::NewListenAddr(NewListenAddr { listener_id, addr }) => {
+                self.listener_presence
+                    .on_new_listen_addr(listener_id, addr.clone());
+            }
+            FromSwarm::ExpiredListenAddr(ExpiredListenAddr { listener_id, .. }) => {
+                self.listener_presence.on_expired_listen_addr(&listener_id);
+            }
             _ => {}
         }
     }
@@ -190,6 +303,7 @@ where
                         .supports_autonat = false;
                 }
 
+                let mut candidate_failure: Option<CandidateFailure> = None;
                 match result {
                     Ok(TestEnd {
                         dial_request: DialRequest { nonce, .. },
@@ -200,18 +314,50 @@ where
                             "server reported reachbility, but didn't actually reached this node."
                         );
                         } else {
+                            // Re-mark the address tested directly rather than relying on the swarm
+                            // to echo `ExternalAddrConfirmed` back: a repeat confirmation of an
+                            // already-confirmed external address is deduped by the swarm and not
+                            // re-emitted, so a still-valid address would otherwise never be
+                            // re-marked and would get re-probed every `retry_interval` instead of
+                            // once per `address_ttl`.
+                            if let Some(info) = self.address_candidates.get_mut(reachable_addr) {
+                                info.is_tested = true;
+                                info.was_confirmed = true;
+                                info.last_tested = Some(Instant::now());
+                            }
                             self.pending_events
                                 .push_back(ToSwarm::ExternalAddrConfirmed(reachable_addr.clone()));
+                            self.inject_reachability(NatStatus::Public(reachable_addr.clone()));
                         }
                     }
-                    Err(ref err) => match &err.internal {
+                    Err(ref err) => {
+                        match &err.internal {
                         dial_request::InternalError::FailureDuringDialBack { addr: Some(addr) }
                         | dial_request::InternalError::UnableToConnectOnSelectedAddress {
                             addr: Some(addr),
                         } => {
-                            if let Some(peer_info) = self.address_candidates.get_mut(addr) {
-                                peer_info.is_tested = true;
+                            let expired =
+                                if let Some(peer_info) = self.address_candidates.get_mut(addr) {
+                                    if peer_info.was_confirmed {
+                                        // A previously-confirmed address failed re-validation:
+                                        // the NAT mapping likely disappeared, so expire it.
+                                        peer_info.is_tested = false;
+                                        peer_info.was_confirmed = false;
+                                        peer_info.last_tested = None;
+                                        true
+                                    } else {
+                                        peer_info.is_tested = true;
+                                        false
+                                    }
+                                } else {
+                                    false
+                                };
+                            if expired {
+                                self.already_tested.remove(addr);
+                                self.pending_events
+                                    .push_back(ToSwarm::ExternalAddrExpired(addr.clone()));
                             }
+                            self.inject_reachability(NatStatus::Private);
                             tracing::debug!(addr = %addr, "Was unable to connect to the server on the selected address.")
                         }
                         dial_request::InternalError::InternalServer
@@ -221,19 +367,88 @@ where
                         | dial_request::InternalError::ServerRejectedDialRequest
                         | dial_request::InternalError::InvalidReferencedAddress { .. }
                         | dial_request::InternalError::ServerChoseNotToDialAnyAddress => {
-                            self.handle_no_connection(peer_id, connection_id);
+                            // A misbehaving server. Do not drop the connection immediately; let
+                            // the rolling failure counter below decide when to drop it from the
+                            // rotation, so `max_server_failures` is actually honoured.
+                            tracing::debug!(server = %peer_id, "Server misbehaved during test: {:?}", err);
                         }
                         _ => {
                             tracing::debug!("Test failed: {:?}", err);
                         }
-                    },
+                        }
+
+                        // Record and surface a per-address classification of the failure so
+                        // applications can distinguish a misbehaving server from a genuinely
+                        // unreachable address.
+                        let reason = classify_failure(&err.internal);
+                        let failed_addr = match &err.internal {
+                            dial_request::InternalError::FailureDuringDialBack {
+                                addr: Some(addr),
+                            }
+                            | dial_request::InternalError::UnableToConnectOnSelectedAddress {
+                                addr: Some(addr),
+                            } => Some(addr.clone()),
+                            _ => tested_addr.clone(),
+                        };
+                        if let Some(addr) = failed_addr {
+                            if let Some(info) = self.address_candidates.get_mut(&addr) {
+                                info.last_failure = Some(reason);
+                                info.last_failure_at = Some(Instant::now());
+                                candidate_failure = Some(CandidateFailure {
+                                    addr,
+                                    probe_count: info.probe_count,
+                                    reason: info
+                                        .last_failure
+                                        .clone()
+                                        .expect("just stored above"),
+                                    last_failure_at: info.last_failure_at,
+                                    source: info.source,
+                                });
+                            }
+                        }
+                    }
+                }
+                let max_server_failures = self.config.max_server_failures;
+                if let Some(info) = self.peer_info.get_mut(&connection_id) {
+                    match &result {
+                        Ok(_) => info.failures = 0,
+                        // Only a misbehaving server counts towards the rolling failure budget.
+                        // An address that simply could not be dialled back is the node's own NAT,
+                        // not the server's fault; counting it would drop every server from the
+                        // rotation after a few probes and permanently disable AutoNAT on a private
+                        // node.
+                        Err(err) if is_server_misbehaving(&err.internal) => {
+                            info.failures += 1;
+                            if info.failures >= max_server_failures {
+                                info.supports_autonat = false;
+                            }
+                        }
+                        Err(_) => {}
+                    }
                 }
-                let event = crate::v2::client::Event {
+                if let Some(metrics) = &self.metrics {
+                    metrics.bytes_sent.inc_by(data_amount as u64);
+                    match &result {
+                        Ok(_) => {
+                            metrics.tests_succeeded.inc();
+                        }
+                        Err(err) => {
+                            metrics
+                                .tests_failed
+                                .get_or_create(&FailureLabels {
+                                    reason: FailureReason::from(&err.internal),
+                                })
+                                .inc();
+                        }
+                    }
+                }
+                let event = Event::Completed(TestResult {
                     tested_addr,
                     bytes_sent: data_amount,
                     server: server.unwrap_or(peer_id),
                     result: result.map(|_| ()),
-                };
+                    failure: candidate_failure,
+                });
                 self.pending_events.push_back(ToSwarm::GenerateEvent(event));
             }
         }
@@ -271,14 +486,26 @@ where
             address_candidates: HashMap::new(),
             already_tested: HashSet::new(),
             peer_info: HashMap::new(),
+            nat_status: NatStatus::Unknown,
+            confidence: 0,
+            reported_status: NatStatus::Unknown,
+            metrics: None,
+            listener_presence: ListenerPresence::default(),
         }
     }
 
+    /// Enable Prometheus instrumentation, registering the metrics against `registry`.
+    pub fn with_metrics(mut self, registry: &mut Registry) -> Self {
+        self.metrics = Some(Metrics::new(registry));
+        self
+    }
+
     /// Inject an immediate test for all pending address candidates.
     fn inject_address_candiate_test(&mut self) {
         if self.peer_info.values().all(|info| !info.supports_autonat) {
             return;
         }
+        self.refresh_stale_candidates();
         if self.address_candidates.is_empty() {
             return;
         }
@@ -290,7 +517,13 @@ where
             .iter()
             .filter(|(_, info)| !info.is_tested)
             .filter(|(addr, _)| !self.already_tested.contains(addr))
-            .map(|(addr, count)| (addr.clone(), *count))
+            // Only probe candidates we could actually be dialed back on. If the swarm has not
+            // reported any listen address yet, fall back to probing all candidates rather than
+            // disabling the behaviour entirely due to startup ordering.
+            .filter(|(addr, _)| {
+                self.listener_presence.is_empty() || self.listener_presence.contains(addr)
+            })
+            .map(|(addr, info)| (addr.clone(), info.score))
             .collect::<Vec<_>>();
         if entries.is_empty() {
             return;
@@ -303,20 +536,95 @@ where
             .take(self.config.max_candidates)
             .cloned()
             .collect();
-        if let Some(ConnectionInfo { peer_id, .. }) = self
-            .peer_info
-            .values()
-            .filter(|e| e.supports_autonat)
-            .choose(&mut self.rng)
-        {
-            self.submit_req_for_peer(*peer_id, addrs);
+        if let Some(metrics) = &self.metrics {
+            let known_servers = self
+                .peer_info
+                .values()
+                .filter(|info| info.supports_autonat)
+                .count();
+            let untested = self
+                .address_candidates
+                .values()
+                .filter(|info| !info.is_tested)
+                .count();
+            metrics.known_servers.set(known_servers as i64);
+            metrics.untested_candidates.set(untested as i64);
         }
-        self.next_tick.reset(self.config.probe_interval);
+        if let Some(peer_id) = self.select_server() {
+            self.submit_req_for_peer(peer_id, addrs);
+            if let Some(metrics) = &self.metrics {
+                metrics.probes_submitted.inc();
+            }
+        }
+        self.next_tick.reset(self.current_interval());
+    }
+
+    /// Re-schedule confirmed addresses whose last validation is older than
+    /// [`Config::address_ttl`], so they get probed again instead of being trusted forever.
+    fn refresh_stale_candidates(&mut self) {
+        let ttl = self.config.address_ttl;
+        let now = Instant::now();
+        let mut stale = Vec::new();
+        for (addr, info) in self.address_candidates.iter_mut() {
+            if !info.is_tested {
+                continue;
+            }
+            if info
+                .last_tested
+                .map(|last| now.duration_since(last) >= ttl)
+                .unwrap_or(false)
+            {
+                info.is_tested = false;
+                stale.push(addr.clone());
+            }
+        }
+        for addr in stale {
+            self.already_tested.remove(&addr);
+        }
+    }
+
+    /// Select the next AutoNAT server to probe, honouring the per-server cooldown, preferring
+    /// servers with the fewest recent failures and round-robining among the rest (least recently
+    /// probed first).
+    fn select_server(&self) -> Option<PeerId> {
+        let now = Instant::now();
+        let cooldown = self.config.per_server_cooldown;
+        let servers = self.peer_info.values().filter(|info| info.supports_autonat);
+
+        // Prefer servers that are off cooldown, but if that leaves nothing (e.g. a single server,
+        // which is the common case), fall back to probing regardless of cooldown rather than
+        // stalling the behaviour entirely.
+        let off_cooldown = servers.clone().any(|info| {
+            info.last_probed
+                .map(|last| now.duration_since(last) >= cooldown)
+                .unwrap_or(true)
+        });
+
+        servers
+            .filter(|info| {
+                !off_cooldown
+                    || info
+                        .last_probed
+                        .map(|last| now.duration_since(last) >= cooldown)
+                        .unwrap_or(true)
+            })
+            .min_by(|a, b| {
+                a.failures.cmp(&b.failures).then_with(|| {
+                    match (a.last_probed, b.last_probed) {
+                        // Never-probed servers rotate in first.
+                        (None, None) => std::cmp::Ordering::Equal,
+                        (None, Some(_)) => std::cmp::Ordering::Less,
+                        (Some(_), None) => std::cmp::Ordering::Greater,
+                        // Otherwise pick the least recently probed.
+                        (Some(a), Some(b)) => a.cmp(&b),
+                    }
+                })
+            })
+            .map(|info| info.peer_id)
     }
 
     fn submit_req_for_peer(&mut self, peer: PeerId, addrs: Vec<Multiaddr>) {
         let nonce = self.rng.gen();
-        let req = DialRequest { nonce, addrs };
         self.pending_nonces.insert(nonce, NonceStatus::Pending);
         if let Some(conn_id) = self
             .peer_info
@@ -325,6 +633,15 @@ where
             .find(|(_, info)| info.peer_id == peer)
             .map(|(id, _)| *id)
         {
+            if let Some(info) = self.peer_info.get_mut(&conn_id) {
+                info.last_probed = Some(Instant::now());
+            }
+            for addr in &addrs {
+                if let Some(info) = self.address_candidates.get_mut(addr) {
+                    info.probe_count += 1;
+                }
+            }
+            let req = DialRequest { nonce, addrs };
             self.pending_events.push_back(ToSwarm::NotifyHandler {
                 peer_id: peer,
                 handler: NotifyHandler::One(conn_id),
@@ -358,6 +675,14 @@ where
         if known_servers_n != changed_n {
             tracing::trace!(server = %peer_id, "Removing potential Autonat server due to dial failure");
         }
+        if let Some(metrics) = &self.metrics {
+            let known_servers = self
+                .peer_info
+                .values()
+                .filter(|info| info.supports_autonat)
+                .count();
+            metrics.known_servers.set(known_servers as i64);
+        }
     }
 
     pub fn validate_addr(&mut self, addr: &Multiaddr) {
@@ -365,6 +690,54 @@ where
             info.is_tested = true;
         }
     }
+
+    /// Feed a single observed reachability status into the confidence state machine, emitting
+    /// [`Event::StatusChanged`] once the aggregate status changes with enough confidence.
+    fn inject_reachability(&mut self, observed: NatStatus) {
+        let was_unknown = matches!(self.nat_status, NatStatus::Unknown);
+        if was_unknown {
+            // Record the first real signal, but stay one short of the threshold: it is reported,
+            // and `current_interval` switches to `refresh_interval`, only once a further agreeing
+            // probe has built up confidence.
+            self.nat_status = observed;
+            self.confidence = self.config.confidence_threshold.saturating_sub(1);
+        } else if self.nat_status.is_same(&observed) {
+            if self.confidence < self.config.confidence_max {
+                self.confidence += 1;
+            }
+            // Keep the confirmed address up to date even when the kind of status did not change.
+            self.nat_status = observed;
+        } else if self.confidence > 0 {
+            self.confidence -= 1;
+        } else {
+            self.nat_status = observed;
+            self.confidence = 0;
+        }
+
+        // Only report once enough confidence has accumulated, to avoid flapping between Public and
+        // Private.
+        let confident = self.confidence >= self.config.confidence_threshold;
+        if confident && !self.reported_status.is_same(&self.nat_status) {
+            let old = std::mem::replace(&mut self.reported_status, self.nat_status.clone());
+            self.pending_events
+                .push_back(ToSwarm::GenerateEvent(Event::StatusChanged {
+                    old,
+                    new: self.nat_status.clone(),
+                }));
+        }
+    }
+
+    /// The probe interval to use for the next tick, depending on how confident we are in the
+    /// current reachability status.
+    fn current_interval(&self) -> Duration {
+        if matches!(self.nat_status, NatStatus::Unknown)
+            || self.confidence < self.config.confidence_threshold
+        {
+            self.config.retry_interval
+        } else {
+            self.config.refresh_interval
+        }
+    }
 }
 
 impl Default for Behaviour<OsRng> {
@@ -396,7 +769,21 @@ impl Debug for Error {
 }
 
 #[derive(Debug)]
-pub struct Event {
+pub enum Event {
+    /// The result of an individual dial-back test against a single candidate address.
+    Completed(TestResult),
+    /// The aggregate reachability status changed after crossing the configured confidence
+    /// threshold.
+    StatusChanged {
+        /// The previously reported status.
+        old: NatStatus,
+        /// The newly reported status.
+        new: NatStatus,
+    },
+}
+
+#[derive(Debug)]
+pub struct TestResult {
     /// The address that was selected for testing.
     /// Is `None` in the case that the server respond with something unexpected.
     pub tested_addr: Option<Multiaddr>,
@@ -409,6 +796,152 @@ pub struct Event {
     /// The result of the test. If the test was successful, this is `Ok(())`.
     /// Otherwise it's an error.
     pub result: Result<(), Error>,
+    /// On failure, a per-address classification of what went wrong (probe count, failure reason,
+    /// and where the candidate came from). `None` on success, and on a failure whose address is
+    /// not a known candidate. This is carried on the same event
+    /// as `result` to avoid emitting a separate, redundant failure event for the same test.
+    pub failure: Option<CandidateFailure>,
+}
+
+/// The overall NAT reachability status of this node, as derived from the individual dial-back
+/// tests.
+#[derive(Debug, Clone, Default)]
+pub enum NatStatus {
+    /// The node is reachable from the public internet on the contained address.
+    Public(Multiaddr),
+    /// The node is behind a NAT and not reachable from the public internet.
+    Private,
+    /// Not enough information has been gathered yet to decide.
+    #[default]
+    Unknown,
+}
+
+impl NatStatus {
+    /// Whether `self` and `other` describe the same kind of reachability, ignoring the concrete
+    /// address carried by [`NatStatus::Public`].
+    fn is_same(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (NatStatus::Public(_), NatStatus::Public(_))
+                | (NatStatus::Private, NatStatus::Private)
+                | (NatStatus::Unknown, NatStatus::Unknown)
+        )
+    }
+}
+
+/// Label set for the failure counter, keyed by the [`InternalError`] variant.
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct FailureLabels {
+    reason: FailureReason,
+}
+
+/// A stable label for an [`InternalError`] variant, used by the failure metric.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, EncodeLabelValue)]
+enum FailureReason {
+    FailureDuringDialBack,
+    UnableToConnect,
+    InternalServer,
+    DataRequestTooLarge,
+    DataRequestTooSmall,
+    InvalidResponse,
+    ServerRejectedDialRequest,
+    InvalidReferencedAddress,
+    ServerChoseNotToDial,
+    Other,
+}
+
+impl From<&InternalError> for FailureReason {
+    fn from(error: &InternalError) -> Self {
+        match error {
+            InternalError::FailureDuringDialBack { .. } => FailureReason::FailureDuringDialBack,
+            InternalError::UnableToConnectOnSelectedAddress { .. } => FailureReason::UnableToConnect,
+            InternalError::InternalServer => FailureReason::InternalServer,
+            InternalError::DataRequestTooLarge { .. } => FailureReason::DataRequestTooLarge,
+            InternalError::DataRequestTooSmall { .. } => FailureReason::DataRequestTooSmall,
+            InternalError::InvalidResponse => FailureReason::InvalidResponse,
+            InternalError::ServerRejectedDialRequest => FailureReason::ServerRejectedDialRequest,
+            InternalError::InvalidReferencedAddress { .. } => {
+                FailureReason::InvalidReferencedAddress
+            }
+            InternalError::ServerChoseNotToDialAnyAddress => FailureReason::ServerChoseNotToDial,
+            _ => FailureReason::Other,
+        }
+    }
+}
+
+/// OpenMetrics instrumentation for the AutoNAT v2 client [`Behaviour`], built on
+/// `prometheus-client` to match the rest of the `libp2p-metrics` stack.
+#[derive(Clone)]
+struct Metrics {
+    /// Number of dial-back probes submitted to a server.
+    probes_submitted: Counter,
+    /// Number of tests the server reported as successful.
+    tests_succeeded: Counter,
+    /// Number of tests that ended with a failure, labelled by the [`InternalError`] variant.
+    tests_failed: Family<FailureLabels, Counter>,
+    /// Total number of bytes the servers asked us to send across all tests.
+    bytes_sent: Counter,
+    /// Number of connected peers that announced AutoNAT server support.
+    known_servers: Gauge,
+    /// Number of candidate addresses that are waiting to be tested.
+    untested_candidates: Gauge,
+}
+
+impl Metrics {
+    fn new(registry: &mut Registry) -> Self {
+        let registry = registry.sub_registry_with_prefix("autonat_v2");
+
+        let probes_submitted = Counter::default();
+        registry.register(
+            "probes_submitted",
+            "Number of AutoNAT v2 dial-back probes submitted to a server",
+            probes_submitted.clone(),
+        );
+
+        let tests_succeeded = Counter::default();
+        registry.register(
+            "tests_succeeded",
+            "Number of AutoNAT v2 tests the server reported as successful",
+            tests_succeeded.clone(),
+        );
+
+        let tests_failed = Family::<FailureLabels, Counter>::default();
+        registry.register(
+            "tests_failed",
+            "Number of failed AutoNAT v2 tests, by failure reason",
+            tests_failed.clone(),
+        );
+
+        let bytes_sent = Counter::default();
+        registry.register(
+            "bytes_sent",
+            "Total number of bytes sent to AutoNAT v2 servers",
+            bytes_sent.clone(),
+        );
+
+        let known_servers = Gauge::default();
+        registry.register(
+            "known_servers",
+            "Number of connected peers supporting the AutoNAT v2 server protocol",
+            known_servers.clone(),
+        );
+
+        let untested_candidates = Gauge::default();
+        registry.register(
+            "untested_candidates",
+            "Number of candidate addresses that are waiting to be tested",
+            untested_candidates.clone(),
+        );
+
+        Self {
+            probes_submitted,
+            tests_succeeded,
+            tests_failed,
+            bytes_sent,
+            known_servers,
+            untested_candidates,
+        }
+    }
 }
 
 fn addr_is_local(addr: &Multiaddr) -> bool {
@@ -428,12 +961,96 @@ struct ConnectionInfo {
     peer_id: PeerId,
     supports_autonat: bool,
     is_local: bool,
+    /// When this server was last selected for a probe, used to enforce the per-server cooldown
+    /// and to round-robin fairly between servers.
+    last_probed: Option<Instant>,
+    /// Number of server-side failures since the last successful test against this server. Tests
+    /// that fail only because our address is unreachable are not counted.
+    failures: usize,
 }
 
-#[derive(Copy, Clone, Default)]
+#[derive(Clone, Default)]
 struct AddressInfo {
     score: usize,
     is_tested: bool,
+    /// When this address was last confirmed as an external address, used to drive TTL-based
+    /// re-validation.
+    last_tested: Option<Instant>,
+    /// Whether this address has ever been confirmed as an external address. Distinguishes a
+    /// re-validation failure (which expires the address) from a first-time test failure.
+    was_confirmed: bool,
+    /// How many times a dial-back has been requested for this address.
+    probe_count: usize,
+    /// Classification of the most recent dial-back failure, if any.
+    last_failure: Option<DialBackFailure>,
+    /// When the most recent dial-back failure occurred.
+    last_failure_at: Option<Instant>,
+    /// Where this candidate address originally came from.
+    source: AddressSource,
+}
+
+/// Where a candidate address originally came from.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AddressSource {
+    /// Surfaced as an external address candidate (e.g. via identify).
+    #[default]
+    Candidate,
+    /// Reported as a confirmed external address by the swarm. This includes confirmations that
+    /// result from this behaviour's own successful tests.
+    ConfirmedBySwarm,
+}
+
+/// Classification of a dial-back failure for a single candidate address.
+#[derive(Debug, Clone)]
+pub enum DialBackFailure {
+    /// The server chose not to dial this address at all.
+    NotDialed,
+    /// The server attempted to dial the address but could not connect.
+    ConnectionFailed,
+    /// A server- or protocol-level error unrelated to the address itself.
+    Other,
+}
+
+/// Per-address report carried by [`Event::Completed`] when a dial-back test fails.
+#[derive(Debug)]
+pub struct CandidateFailure {
+    /// The candidate address the test was run against.
+    pub addr: Multiaddr,
+    /// How many times a dial-back has been requested for this address so far.
+    pub probe_count: usize,
+    /// Classification of the most recent failure.
+    pub reason: DialBackFailure,
+    /// When the most recent failure occurred, if known.
+    pub last_failure_at: Option<Instant>,
+    /// Where this candidate address originally came from.
+    pub source: AddressSource,
+}
+
+/// Whether a failed test indicates the *server* misbehaved (as opposed to the node's address
+/// simply being unreachable, which is the expected outcome for a genuinely NAT'd node). Only these
+/// errors count towards a server's rolling failure budget.
+fn is_server_misbehaving(error: &InternalError) -> bool {
+    matches!(
+        error,
+        InternalError::InternalServer
+            | InternalError::DataRequestTooLarge { .. }
+            | InternalError::DataRequestTooSmall { .. }
+            | InternalError::InvalidResponse
+            | InternalError::ServerRejectedDialRequest
+            | InternalError::InvalidReferencedAddress { .. }
+            | InternalError::ServerChoseNotToDialAnyAddress
+    )
+}
+
+fn classify_failure(error: &InternalError) -> DialBackFailure {
+    match error {
+        InternalError::ServerChoseNotToDialAnyAddress => DialBackFailure::NotDialed,
+        InternalError::FailureDuringDialBack { .. }
+        | InternalError::UnableToConnectOnSelectedAddress { .. } => {
+            DialBackFailure::ConnectionFailed
+        }
+        _ => DialBackFailure::Other,
+    }
 }
 
 impl PartialOrd for AddressInfo {
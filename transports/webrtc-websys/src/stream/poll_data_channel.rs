@@ -1,11 +1,15 @@
 // This crate inspired by webrtc::data::data_channel::poll_data_channel.rs
 use crate::error::Error;
 use futures::channel;
-use futures::{AsyncRead, AsyncWrite, FutureExt, StreamExt};
+use futures::task::AtomicWaker;
+use futures::{AsyncBufRead, AsyncRead, AsyncWrite, FutureExt, StreamExt};
 use std::fmt;
 use std::io;
+use std::io::IoSlice;
 use std::pin::Pin;
 use std::result::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::task::{ready, Context, Poll};
 use wasm_bindgen::{prelude::*, JsCast};
 use web_sys::{MessageEvent, RtcDataChannel, RtcDataChannelEvent, RtcDataChannelState};
@@ -13,6 +17,14 @@ use web_sys::{MessageEvent, RtcDataChannel, RtcDataChannelEvent, RtcDataChannelS
 /// Default capacity of the temporary read buffer used by [`webrtc_sctp::stream::PollStream`].
 const DEFAULT_READ_BUF_SIZE: usize = 8192;
 
+/// Default high-water mark for the amount of data buffered in the [`RtcDataChannel`] before
+/// [`AsyncWrite::poll_write`] applies backpressure.
+const DEFAULT_MAX_BUFFERED_AMOUNT: usize = 64 * 1024;
+
+/// Default threshold below which [`AsyncWrite::poll_write_vectored`] concatenates its slices into
+/// a single DataChannel message.
+const DEFAULT_WRITE_AGGREGATION_THRESHOLD: usize = 1024;
+
 /// A wrapper around around [`RtcDataChannel`], which implements [`AsyncRead`] and
 /// [`AsyncWrite`].
 ///
@@ -35,7 +47,29 @@ pub struct PollDataChannel {
     /// oneshot since only one onclose event is sent
     rx_onclose: channel::oneshot::Receiver<()>,
 
+    /// Carry-over buffer for the message currently being drained by [`AsyncRead`].
+    read_state: ReadState,
+
     read_buf_cap: usize,
+
+    /// High-water mark for [`RtcDataChannel::buffered_amount`] above which writes block.
+    max_buffered_amount: usize,
+
+    /// Threshold below which vectored writes are coalesced into a single message.
+    write_aggregation_threshold: usize,
+}
+
+/// The state of the carry-over read buffer.
+///
+/// A single received SCTP message may be larger than the buffer passed to [`AsyncRead::poll_read`],
+/// so we hold on to the remainder and serve it across subsequent `poll_read` calls instead of
+/// dropping it. Mirrors the design used by `async_io_stream`.
+enum ReadState {
+    /// A message has been received and is being drained. `pos` is the offset of the first
+    /// not-yet-read byte within `chunk`.
+    Ready { chunk: Vec<u8>, pos: usize },
+    /// No buffered data; the next `poll_read` has to poll the message channel.
+    Empty,
 }
 
 impl PollDataChannel {
@@ -109,7 +143,10 @@ impl PollDataChannel {
             rx_onopen,
             rx_onclose,
             rx_onbufferedamountlow,
+            read_state: ReadState::Empty,
             read_buf_cap: DEFAULT_READ_BUF_SIZE,
+            max_buffered_amount: DEFAULT_MAX_BUFFERED_AMOUNT,
+            write_aggregation_threshold: DEFAULT_WRITE_AGGREGATION_THRESHOLD,
         }
     }
 
@@ -128,6 +165,21 @@ impl PollDataChannel {
         self.read_buf_cap = capacity
     }
 
+    /// Set the high-water mark for the channel's buffered amount (default: 64 KiB).
+    ///
+    /// Once [`RtcDataChannel::buffered_amount`] reaches this value, [`AsyncWrite::poll_write`]
+    /// returns [`Poll::Pending`] until the browser has drained the send buffer below the mark,
+    /// bounding the memory a fast producer can tie up.
+    pub fn set_max_buffered_amount(&mut self, max_buffered_amount: usize) {
+        self.max_buffered_amount = max_buffered_amount
+    }
+
+    /// Set the threshold below which [`AsyncWrite::poll_write_vectored`] coalesces its slices into
+    /// a single DataChannel message (default: 1 KiB).
+    pub fn set_write_aggregation_threshold(&mut self, threshold: usize) {
+        self.write_aggregation_threshold = threshold
+    }
+
     /// Get Ready State of [RtcDataChannel]
     pub(crate) fn ready_state(&self) -> RtcDataChannelState {
         self.data_channel.ready_state()
@@ -161,20 +213,117 @@ impl AsyncRead for PollDataChannel {
         cx: &mut Context<'_>,
         buf: &mut [u8],
     ) -> Poll<Result<usize, std::io::Error>> {
-        match ready!(self.rx_onmessage.poll_next_unpin(cx)) {
-            Some(data) => {
-                let data_len = data.len();
-                let buf_len = buf.len();
-                log::trace!("poll_read [{:?} of {} bytes]", data_len, buf_len);
-                let len = std::cmp::min(data_len, buf_len);
-                buf[..len].copy_from_slice(&data[..len]);
-                Poll::Ready(Ok(len))
+        let this = self.get_mut();
+        poll_read_buffered(&mut this.read_state, &mut this.rx_onmessage, cx, buf)
+    }
+}
+
+/// Copy out of a carry-over [`ReadState`], polling `rx` for a fresh message only once the buffered
+/// chunk is fully drained. Shared by [`PollDataChannel`] and [`DuplexStream`] so both exercise the
+/// same partial-read logic. EOF (`None`) is only reported after the buffer is exhausted.
+fn poll_read_buffered(
+    read_state: &mut ReadState,
+    rx: &mut channel::mpsc::Receiver<Vec<u8>>,
+    cx: &mut Context<'_>,
+    buf: &mut [u8],
+) -> Poll<io::Result<usize>> {
+    loop {
+        if let ReadState::Ready { chunk, pos } = read_state {
+            let remaining = &chunk[*pos..];
+            let len = std::cmp::min(remaining.len(), buf.len());
+            log::trace!("poll_read [{} of {} buffered bytes]", len, remaining.len());
+            buf[..len].copy_from_slice(&remaining[..len]);
+            *pos += len;
+            if *pos >= chunk.len() {
+                *read_state = ReadState::Empty;
             }
-            None => Poll::Ready(Ok(0)), // if None, the stream is exhausted, no data to read
+            return Poll::Ready(Ok(len));
+        }
+
+        match ready!(rx.poll_next_unpin(cx)) {
+            Some(data) if data.is_empty() => continue,
+            Some(data) => *read_state = ReadState::Ready { chunk: data, pos: 0 },
+            None => return Poll::Ready(Ok(0)), // if None, the stream is exhausted, no data to read
         }
     }
 }
 
+/// [`AsyncBufRead::poll_fill_buf`] against a carry-over [`ReadState`], shared by both stream types.
+fn poll_fill_buf_buffered<'a>(
+    read_state: &'a mut ReadState,
+    rx: &mut channel::mpsc::Receiver<Vec<u8>>,
+    cx: &mut Context<'_>,
+) -> Poll<io::Result<&'a [u8]>> {
+    while matches!(read_state, ReadState::Empty) {
+        match ready!(rx.poll_next_unpin(cx)) {
+            Some(data) if data.is_empty() => continue,
+            Some(data) => *read_state = ReadState::Ready { chunk: data, pos: 0 },
+            None => return Poll::Ready(Ok(&[])), // stream exhausted
+        }
+    }
+
+    match read_state {
+        ReadState::Ready { chunk, pos } => Poll::Ready(Ok(&chunk[*pos..])),
+        ReadState::Empty => Poll::Ready(Ok(&[])),
+    }
+}
+
+/// [`AsyncBufRead::consume`] against a carry-over [`ReadState`], shared by both stream types.
+fn consume_buffered(read_state: &mut ReadState, amt: usize) {
+    if let ReadState::Ready { chunk, pos } = read_state {
+        *pos = std::cmp::min(*pos + amt, chunk.len());
+        if *pos >= chunk.len() {
+            *read_state = ReadState::Empty;
+        }
+    }
+}
+
+/// Coalesce the leading slices of a vectored write into a single buffer, shared by both stream
+/// types so they apply identical aggregation.
+///
+/// Slices are concatenated until adding the next one would exceed `threshold`, but the first
+/// non-empty slice is always included even if it alone is larger, so an oversized leading slice
+/// never stalls the write. Empty slices are skipped.
+fn aggregate_vectored(bufs: &[IoSlice<'_>], threshold: usize) -> Vec<u8> {
+    let mut aggregated = Vec::new();
+    for slice in bufs {
+        if slice.is_empty() {
+            continue;
+        }
+        if !aggregated.is_empty() && aggregated.len() + slice.len() > threshold {
+            break;
+        }
+        aggregated.extend_from_slice(slice);
+        if aggregated.len() >= threshold {
+            break;
+        }
+    }
+    aggregated
+}
+
+/// Shared byte accounting for one direction of a [`duplex`] pair.
+///
+/// The writer increments `buffered` by the number of bytes it queues; the reading peer decrements
+/// it as it drains them and wakes `waker`. This lets [`DuplexStream`] honour the same
+/// `max_buffered_amount` high-water mark as [`PollDataChannel`] even though the in-memory channel
+/// has no `buffered_amount` of its own.
+#[derive(Debug, Default)]
+struct Backpressure {
+    buffered: AtomicUsize,
+    waker: AtomicWaker,
+}
+
+impl AsyncBufRead for PollDataChannel {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        poll_fill_buf_buffered(&mut this.read_state, &mut this.rx_onmessage, cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        consume_buffered(&mut self.get_mut().read_state, amt)
+    }
+}
+
 impl AsyncWrite for PollDataChannel {
     fn poll_write(
         mut self: Pin<&mut Self>,
@@ -192,6 +341,26 @@ impl AsyncWrite for PollDataChannel {
             ready!(self.rx_onopen.poll_next_unpin(cx)).unwrap();
         }
 
+        // Apply backpressure: if the channel already has too much data queued, wait for the
+        // `bufferedamountlow` event rather than growing the browser's send buffer without bound.
+        let max_buffered_amount = self.max_buffered_amount as u32;
+        // The low-water threshold must sit strictly below the high-water mark: `onbufferedamountlow`
+        // only fires on a downward crossing of the threshold, and we block before letting
+        // `buffered_amount` exceed the mark, so a threshold equal to the mark might never produce a
+        // wake-up edge. Halving the mark is the common idiom.
+        let low_water_threshold = max_buffered_amount / 2;
+        while self.data_channel.buffered_amount() >= max_buffered_amount {
+            self.data_channel
+                .set_buffered_amount_low_threshold(low_water_threshold);
+            match self.rx_onbufferedamountlow.poll_next_unpin(cx) {
+                // Got a low-water notification: re-check the buffered amount.
+                Poll::Ready(Some(())) => continue,
+                // The callback channel is gone; stop waiting and attempt the send.
+                Poll::Ready(None) => break,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
         // Now that the channel is open, send the data
         match self.send(buf) {
             Ok(_) => Poll::Ready(Ok(buf.len())),
@@ -202,6 +371,25 @@ impl AsyncWrite for PollDataChannel {
         }
     }
 
+    /// Coalesce many small libp2p frames into a single DataChannel message to cut per-message
+    /// overhead.
+    ///
+    /// When the combined length of `bufs` is below the aggregation threshold, all slices are
+    /// concatenated into one buffer and sent as a single message. Otherwise the largest leading
+    /// prefix of slices that fits within the threshold (but always at least the first slice) is
+    /// sent. In both cases the number of bytes consumed from `bufs` is returned.
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let aggregated = aggregate_vectored(bufs, self.write_aggregation_threshold);
+        if aggregated.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        self.as_mut().poll_write(cx, &aggregated)
+    }
+
     /// Attempt to flush the object, ensuring that any buffered data reach their destination.
     /// On success, returns Poll::Ready(Ok(())).
     /// If flushing cannot immediately complete, this method returns Poll::Pending and arranges for the current task (via cx.waker().wake_by_ref()) to receive a notification when the object can make progress towards flushing.
@@ -257,3 +445,304 @@ impl AsRef<RtcDataChannel> for PollDataChannel {
         &self.data_channel
     }
 }
+
+/// Create a pair of linked in-memory streams, modeled on [`tokio::io::duplex`].
+///
+/// Bytes written to one half become readable on the other, backed by bounded
+/// [`futures::channel::mpsc`] queues instead of a real [`RtcDataChannel`]. This allows the
+/// [`AsyncRead`]/[`AsyncWrite`] logic (framing, partial reads, backpressure) to be exercised off
+/// the browser while going through the same carry-over read buffer used by [`PollDataChannel`].
+///
+/// `buffer` bounds how many messages may be queued per direction before [`AsyncWrite::poll_write`]
+/// applies backpressure. Once one half is dropped, the peer can still read whatever the dropped
+/// half had queued and then sees EOF; writing to a dropped peer fails with
+/// [`io::ErrorKind::BrokenPipe`].
+pub fn duplex(buffer: usize) -> (DuplexStream, DuplexStream) {
+    let (a_tx, a_rx) = channel::mpsc::channel(buffer);
+    let (b_tx, b_rx) = channel::mpsc::channel(buffer);
+    // One accounting cell per direction, shared between the writing half and the reading peer.
+    let a_to_b = Arc::new(Backpressure::default());
+    let b_to_a = Arc::new(Backpressure::default());
+    (
+        DuplexStream {
+            tx: a_tx,
+            rx: b_rx,
+            read_state: ReadState::Empty,
+            write_bp: a_to_b.clone(),
+            read_bp: b_to_a.clone(),
+            max_buffered_amount: DEFAULT_MAX_BUFFERED_AMOUNT,
+            write_aggregation_threshold: DEFAULT_WRITE_AGGREGATION_THRESHOLD,
+        },
+        DuplexStream {
+            tx: b_tx,
+            rx: a_rx,
+            read_state: ReadState::Empty,
+            write_bp: b_to_a,
+            read_bp: a_to_b,
+            max_buffered_amount: DEFAULT_MAX_BUFFERED_AMOUNT,
+            write_aggregation_threshold: DEFAULT_WRITE_AGGREGATION_THRESHOLD,
+        },
+    )
+}
+
+/// One half of an in-memory loopback pair created by [`duplex`].
+pub struct DuplexStream {
+    tx: channel::mpsc::Sender<Vec<u8>>,
+    rx: channel::mpsc::Receiver<Vec<u8>>,
+    read_state: ReadState,
+    /// Bytes we have queued towards the peer; gates [`AsyncWrite::poll_write`].
+    write_bp: Arc<Backpressure>,
+    /// Bytes the peer has queued towards us; drained as we read.
+    read_bp: Arc<Backpressure>,
+    max_buffered_amount: usize,
+    write_aggregation_threshold: usize,
+}
+
+impl DuplexStream {
+    /// Set the high-water mark for bytes queued towards the peer (default: 64 KiB).
+    ///
+    /// Mirrors [`PollDataChannel::set_max_buffered_amount`]: once this many bytes are outstanding,
+    /// [`AsyncWrite::poll_write`] returns [`Poll::Pending`] until the peer has read enough to fall
+    /// back below the mark.
+    pub fn set_max_buffered_amount(&mut self, max_buffered_amount: usize) {
+        self.max_buffered_amount = max_buffered_amount
+    }
+
+    /// Set the threshold below which [`AsyncWrite::poll_write_vectored`] coalesces its slices into
+    /// a single message (default: 1 KiB).
+    pub fn set_write_aggregation_threshold(&mut self, threshold: usize) {
+        self.write_aggregation_threshold = threshold
+    }
+
+    /// Account for `n` bytes handed to the caller: drop them from the peer's outstanding total and
+    /// wake a writer that may be blocked on the high-water mark.
+    fn on_read(&self, n: usize) {
+        if n > 0 {
+            self.read_bp.buffered.fetch_sub(n, Ordering::AcqRel);
+            self.read_bp.waker.wake();
+        }
+    }
+}
+
+impl AsyncRead for DuplexStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = poll_read_buffered(&mut this.read_state, &mut this.rx, cx, buf);
+        if let Poll::Ready(Ok(n)) = &poll {
+            this.on_read(*n);
+        }
+        poll
+    }
+}
+
+impl AsyncBufRead for DuplexStream {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        poll_fill_buf_buffered(&mut this.read_state, &mut this.rx, cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        // Determine how many bytes `consume` will actually drop before mutating the state.
+        let consumed = match &this.read_state {
+            ReadState::Ready { chunk, pos } => std::cmp::min(amt, chunk.len() - *pos),
+            ReadState::Empty => 0,
+        };
+        consume_buffered(&mut this.read_state, amt);
+        this.on_read(consumed);
+    }
+}
+
+impl AsyncWrite for DuplexStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        // Apply the same high-water backpressure as `PollDataChannel`: block once too many bytes
+        // are outstanding, and re-check after registering to avoid missing the peer's wake-up.
+        if this.write_bp.buffered.load(Ordering::Acquire) >= this.max_buffered_amount {
+            this.write_bp.waker.register(cx.waker());
+            if this.write_bp.buffered.load(Ordering::Acquire) >= this.max_buffered_amount {
+                return Poll::Pending;
+            }
+        }
+        // A bounded channel gives us queue-depth backpressure for free: `poll_ready` is `Pending`
+        // while the queue is full and `Err` once the peer has been dropped.
+        match this.tx.poll_ready(cx) {
+            Poll::Ready(Ok(())) => match this.tx.start_send(buf.to_vec()) {
+                Ok(()) => {
+                    this.write_bp.buffered.fetch_add(buf.len(), Ordering::AcqRel);
+                    Poll::Ready(Ok(buf.len()))
+                }
+                Err(_) => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+            },
+            Poll::Ready(Err(_)) => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let aggregated = aggregate_vectored(bufs, self.write_aggregation_threshold);
+        if aggregated.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+        self.poll_write(cx, &aggregated)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Closing the sender drains any already-queued messages on the peer and then signals EOF.
+        self.get_mut().tx.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl fmt::Debug for DuplexStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DuplexStream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::{self, ArcWake};
+    use futures::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+    use std::sync::atomic::AtomicBool;
+
+    /// A waker that records whether it has been woken, so backpressure tests can assert a blocked
+    /// writer is notified when the peer reads.
+    struct FlagWaker(AtomicBool);
+
+    impl ArcWake for FlagWaker {
+        fn wake_by_ref(arc: &Arc<Self>) {
+            arc.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn partial_reads_preserve_carry_over() {
+        futures::executor::block_on(async {
+            let (mut a, mut b) = duplex(4);
+            a.write_all(b"hello world").await.unwrap();
+
+            let mut first = [0u8; 5];
+            assert_eq!(b.read(&mut first).await.unwrap(), 5);
+            assert_eq!(&first, b"hello");
+
+            // The remainder of the same message is served from the carry-over buffer.
+            let mut rest = [0u8; 32];
+            assert_eq!(b.read(&mut rest).await.unwrap(), 6);
+            assert_eq!(&rest[..6], b" world");
+        });
+    }
+
+    #[test]
+    fn fill_buf_and_consume() {
+        futures::executor::block_on(async {
+            let (mut a, mut b) = duplex(4);
+            a.write_all(b"abcdef").await.unwrap();
+
+            assert_eq!(b.fill_buf().await.unwrap(), b"abcdef");
+            Pin::new(&mut b).consume(3);
+            assert_eq!(b.fill_buf().await.unwrap(), b"def");
+            Pin::new(&mut b).consume(3);
+
+            drop(a);
+            assert_eq!(b.fill_buf().await.unwrap(), b"");
+        });
+    }
+
+    #[test]
+    fn vectored_write_aggregates_small_slices() {
+        futures::executor::block_on(async {
+            let (mut a, mut b) = duplex(4);
+            let slices = [
+                IoSlice::new(b"foo"),
+                IoSlice::new(b"bar"),
+                IoSlice::new(b"baz"),
+            ];
+            let written = a.write_vectored(&slices).await.unwrap();
+            assert_eq!(written, 9);
+
+            // All three slices arrive as a single coalesced message.
+            assert_eq!(b.fill_buf().await.unwrap(), b"foobarbaz");
+        });
+    }
+
+    #[test]
+    fn vectored_write_stops_at_threshold() {
+        futures::executor::block_on(async {
+            let (mut a, mut b) = duplex(4);
+            a.set_write_aggregation_threshold(4);
+            let slices = [
+                IoSlice::new(b"abc"),
+                IoSlice::new(b"defgh"),
+                IoSlice::new(b"ij"),
+            ];
+            // First slice (3) fits; adding the 5-byte slice would exceed the 4-byte threshold, so
+            // only the first slice is sent.
+            let written = a.write_vectored(&slices).await.unwrap();
+            assert_eq!(written, 3);
+            assert_eq!(b.fill_buf().await.unwrap(), b"abc");
+        });
+    }
+
+    #[test]
+    fn write_blocks_on_buffered_amount_and_wakes_on_read() {
+        let (mut a, mut b) = duplex(16);
+        a.set_max_buffered_amount(4);
+
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = task::waker(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        // First write fills the high-water mark.
+        assert_eq!(
+            Pin::new(&mut a).poll_write(&mut cx, b"data"),
+            Poll::Ready(Ok(4))
+        );
+        // Second write is over the mark and must block.
+        assert_eq!(Pin::new(&mut a).poll_write(&mut cx, b"more"), Poll::Pending);
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        // Draining the peer drops the outstanding byte count and wakes the blocked writer.
+        let mut buf = [0u8; 4];
+        let noop = task::noop_waker();
+        let mut read_cx = Context::from_waker(&noop);
+        assert_eq!(
+            Pin::new(&mut b).poll_read(&mut read_cx, &mut buf),
+            Poll::Ready(Ok(4))
+        );
+        assert!(flag.0.load(Ordering::SeqCst));
+
+        // The previously blocked write now succeeds.
+        assert_eq!(
+            Pin::new(&mut a).poll_write(&mut cx, b"more"),
+            Poll::Ready(Ok(4))
+        );
+    }
+
+    #[test]
+    fn write_to_dropped_peer_is_broken_pipe() {
+        futures::executor::block_on(async {
+            let (mut a, b) = duplex(4);
+            drop(b);
+            let err = a.write_all(b"x").await.unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::BrokenPipe);
+        });
+    }
+}